@@ -0,0 +1,511 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! ECDSA adaptor (encrypted) signatures.
+//!
+//! # Unaudited, experimental
+//!
+//! This module is gated behind the `unstable-adaptor-signatures` feature and
+//! is **not** covered by this crate's usual stability or audit guarantees.
+//! Plain libsecp256k1 (what this crate links against) does not implement
+//! ECDSA adaptor signatures; that C module only exists in the
+//! `secp256k1-zkp` fork, which this crate does not vendor or bind. Rather
+//! than call FFI entry points this tree doesn't provide, everything here is
+//! a from-scratch Rust implementation of the protocol on top of the
+//! scalar/point tweak primitives the crate already exposes. It has not been
+//! checked against an independent implementation or published test vectors,
+//! and has not had a cryptography review. Do not use it to protect real
+//! funds; it exists so the protocol can be exercised and reviewed in the
+//! open, behind an opt-in flag, rather than merged as a first-class
+//! supported API. Enable the feature only if you understand and accept
+//! that risk.
+//!
+//! # The protocol
+//!
+//! An adaptor signature is a pre-signature that is bound to an "adaptor
+//! point" `Y = y*G` and is *not* itself a valid ECDSA signature. It carries
+//! a zero-knowledge (DLEQ) proof that it was constructed honestly, can be
+//! checked against the signer's public key without knowing `y`, and can
+//! only be completed into a real [`Signature`] by someone who knows the
+//! decryption secret `y`. Publishing the completed signature necessarily
+//! reveals `y` to anyone who also holds the pre-signature, which is what
+//! makes this useful for atomic swaps, payment-channel PTLCs, and discreet
+//! log contracts: the secret needed to claim one side of the contract is
+//! the same secret that unlocks the other.
+//!
+//! Implemented directly in terms of the scalar/point tweak primitives the
+//! crate already exposes: [`SecretKey::add_tweak`]/[`SecretKey::mul_tweak`]
+//! for scalar arithmetic mod the group order, and
+//! [`PublicKey::mul_tweak`]/[`PublicKey::combine`]/[`PublicKey::negate`] for
+//! point arithmetic. The one primitive the public API doesn't expose,
+//! modular inversion, is derived from [`SecretKey::mul_tweak`] via Fermat's
+//! little theorem (`scalar_invert`, below).
+//!
+//! The underlying scheme: the signer picks a nonce `k` and publishes
+//! `R' = k*G` (the plain nonce commitment) and `R = k*Y` (the nonce
+//! commitment tweaked by the adaptor point), proving via DLEQ that the same
+//! `k` underlies both. The pre-signature scalar is `s' = k⁻¹(m + r*x)` where
+//! `r = x(R)`, exactly the normal ECDSA equation but with `r` taken from the
+//! *tweaked* nonce point. Once `y` is known, `s = s' * y⁻¹` is a valid
+//! ECDSA signature with nonce `k*y` (since `(k*y)*G = k*Y = R`), and from any
+//! completed `(r, s)` anyone holding `s'` can recover `y = s' * s⁻¹`.
+
+use core::{fmt, str};
+
+use super::util::{rfc6979_nonce, sha256};
+use crate::ecdsa::Signature;
+use crate::{from_hex, Error, Message, PublicKey, Scalar, Secp256k1, SecretKey, Signing, Verification};
+
+/// Size in bytes of the fixed-length [`AdaptorSignature`] encoding: a
+/// 33-byte plain nonce point `R'`, a 33-byte adaptor-tweaked nonce point
+/// `R`, a 32-byte pre-signature scalar `s'`, and a 64-byte DLEQ proof
+/// (32-byte challenge `e` + 32-byte response `z`) that the same discrete
+/// log underlies `R'` (base `G`) and `R` (base `Y`).
+const ADAPTOR_SIGNATURE_SIZE: usize = 162;
+
+const R_PRIME_RANGE: core::ops::Range<usize> = 0..33;
+const R_RANGE: core::ops::Range<usize> = 33..66;
+const S_PRIME_RANGE: core::ops::Range<usize> = 66..98;
+const E_RANGE: core::ops::Range<usize> = 98..130;
+const Z_RANGE: core::ops::Range<usize> = 130..162;
+
+/// A pre-signature encrypted to an adaptor point `Y`.
+///
+/// See the [module-level documentation](self) for the protocol this
+/// implements.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct AdaptorSignature([u8; ADAPTOR_SIGNATURE_SIZE]);
+
+impl AdaptorSignature {
+    /// Parses an adaptor signature from its fixed-length byte encoding.
+    pub fn from_slice(data: &[u8]) -> Result<AdaptorSignature, Error> {
+        if data.len() != ADAPTOR_SIGNATURE_SIZE {
+            return Err(Error::InvalidSignature);
+        }
+        let mut buf = [0u8; ADAPTOR_SIGNATURE_SIZE];
+        buf.copy_from_slice(data);
+        Ok(AdaptorSignature(buf))
+    }
+
+    /// Serializes the adaptor signature to its fixed-length byte encoding.
+    #[inline]
+    pub fn serialize(&self) -> [u8; ADAPTOR_SIGNATURE_SIZE] {
+        self.0
+    }
+
+    fn r_prime(&self) -> Result<PublicKey, Error> {
+        PublicKey::from_slice(&self.0[R_PRIME_RANGE])
+    }
+
+    fn r(&self) -> Result<PublicKey, Error> {
+        PublicKey::from_slice(&self.0[R_RANGE])
+    }
+
+    fn s_prime(&self) -> Result<SecretKey, Error> {
+        SecretKey::from_slice(&self.0[S_PRIME_RANGE])
+    }
+
+    fn e(&self) -> Result<SecretKey, Error> {
+        SecretKey::from_slice(&self.0[E_RANGE])
+    }
+
+    fn z(&self) -> Result<SecretKey, Error> {
+        SecretKey::from_slice(&self.0[Z_RANGE])
+    }
+}
+
+impl fmt::Debug for AdaptorSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for AdaptorSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.0.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl str::FromStr for AdaptorSignature {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<AdaptorSignature, Error> {
+        let mut res = [0u8; ADAPTOR_SIGNATURE_SIZE];
+        match from_hex(s, &mut res) {
+            Ok(x) if x == ADAPTOR_SIGNATURE_SIZE => Ok(AdaptorSignature(res)),
+            _ => Err(Error::InvalidSignature),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl serde::Serialize for AdaptorSignature {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        if s.is_human_readable() {
+            s.collect_str(self)
+        } else {
+            s.serialize_bytes(&self.0)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<'de> serde::Deserialize<'de> for AdaptorSignature {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        if d.is_human_readable() {
+            d.deserialize_str(crate::serde_util::FromStrVisitor::new(
+                "a hex string representing an ECDSA adaptor signature",
+            ))
+        } else {
+            d.deserialize_bytes(crate::serde_util::BytesVisitor::new(
+                "raw byte stream, that represents an ECDSA adaptor signature",
+                AdaptorSignature::from_slice,
+            ))
+        }
+    }
+}
+
+/// Produces an adaptor (pre-)signature for `msg` using `sk`, encrypted to
+/// the adaptor point `Y`. The result is not a valid ECDSA signature; it
+/// becomes one only once [`decrypt`] is called with the secret `y` such
+/// that `Y = y*G`. Requires a signing- and verification-capable context
+/// (point tweaks need the latter).
+pub fn encrypt<C: Signing + Verification>(
+    secp: &Secp256k1<C>,
+    msg: &Message,
+    sk: &SecretKey,
+    adaptor: &PublicKey,
+) -> Result<AdaptorSignature, Error> {
+    let k = signing_nonce(msg, sk, adaptor)?;
+    let r_prime = PublicKey::from_secret_key(secp, &k);
+    let r = adaptor.mul_tweak(secp, &Scalar::from(k))?;
+
+    let r_x = point_x_bytes(&r);
+    let r_times_sk = (*sk).mul_tweak(&Scalar::from_be_bytes(r_x).map_err(|_| Error::InvalidTweak)?)?;
+    // `m` is computed as a `Scalar`, not a `SecretKey`, and reduced mod `n`
+    // first: a message digest can legitimately be `0` or `>= n`, both of
+    // which `SecretKey` would reject as invalid key material. `r_times_sk`
+    // is the addend taking the `self` position instead, since it (unlike
+    // `m`) is always expected to be a valid nonzero scalar.
+    let m_scalar = Scalar::from_be_bytes(reduce_mod_n(msg.as_ref())).map_err(|_| Error::InvalidTweak)?;
+    let e = r_times_sk.add_tweak(&m_scalar)?;
+    let k_inv = scalar_invert(&k)?;
+    let s_prime = e.mul_tweak(&Scalar::from(k_inv))?;
+
+    // DLEQ proof that the same `k` underlies `r_prime = k*G` and `r = k*Y`.
+    let j = dleq_nonce(&k, adaptor)?;
+    let a1 = PublicKey::from_secret_key(secp, &j);
+    let a2 = adaptor.mul_tweak(secp, &Scalar::from(j))?;
+    let e_chal = dleq_challenge(adaptor, &r_prime, &r, &a1, &a2)?;
+    let k_times_e = k.mul_tweak(&Scalar::from(e_chal))?;
+    let z = j.add_tweak(&Scalar::from(k_times_e))?;
+
+    let mut out = [0u8; ADAPTOR_SIGNATURE_SIZE];
+    out[R_PRIME_RANGE].copy_from_slice(&r_prime.serialize());
+    out[R_RANGE].copy_from_slice(&r.serialize());
+    out[S_PRIME_RANGE].copy_from_slice(&s_prime.secret_bytes());
+    out[E_RANGE].copy_from_slice(&e_chal.secret_bytes());
+    out[Z_RANGE].copy_from_slice(&z.secret_bytes());
+    Ok(AdaptorSignature(out))
+}
+
+/// Checks that `adaptor_sig` is a well-formed pre-signature for `msg` under
+/// `pk`, encrypted to the adaptor point `adaptor`: verifies both the DLEQ
+/// proof and the adaptor analogue of the usual ECDSA verification equation.
+/// Does not require knowledge of the decryption secret `y`. Requires a
+/// signing- and verification-capable context.
+pub fn verify<C: Signing + Verification>(
+    secp: &Secp256k1<C>,
+    adaptor_sig: &AdaptorSignature,
+    pk: &PublicKey,
+    msg: &Message,
+    adaptor: &PublicKey,
+) -> Result<(), Error> {
+    let r_prime = adaptor_sig.r_prime()?;
+    let r = adaptor_sig.r()?;
+    let s_prime = adaptor_sig.s_prime()?;
+    let e_chal = adaptor_sig.e()?;
+    let z = adaptor_sig.z()?;
+
+    let z_g = PublicKey::from_secret_key(secp, &z);
+    let neg_e_r_prime = r_prime.mul_tweak(secp, &Scalar::from(e_chal))?.negate(secp);
+    let a1 = z_g.combine(&neg_e_r_prime)?;
+
+    let z_y = adaptor.mul_tweak(secp, &Scalar::from(z))?;
+    let neg_e_r = r.mul_tweak(secp, &Scalar::from(e_chal))?.negate(secp);
+    let a2 = z_y.combine(&neg_e_r)?;
+
+    let e_expected = dleq_challenge(adaptor, &r_prime, &r, &a1, &a2)?;
+    if e_expected != e_chal {
+        return Err(Error::IncorrectSignature);
+    }
+
+    // s' * R' == m*G + r*pk, the adaptor analogue of the usual ECDSA
+    // verification equation R' == s'^-1 (m*G + r*pk), rearranged to avoid
+    // inverting the (public, but not necessarily cheap-to-invert) s'.
+    //
+    // `m` is reduced mod `n` first (see `encrypt`) rather than rejected when
+    // out of `SecretKey`'s nonzero/in-range domain. The one case that
+    // remains structurally unrepresentable is `m == 0`: `m*G` would be the
+    // point at infinity, which `PublicKey` has no way to represent. That
+    // case is handled by skipping the `m*G` term entirely, since adding the
+    // identity is a no-op; it is not silently mishandled, just algebraically
+    // elided.
+    let r_x = point_x_bytes(&r);
+    let r_pk = pk.mul_tweak(secp, &Scalar::from_be_bytes(r_x).map_err(|_| Error::InvalidTweak)?)?;
+    let m_reduced = reduce_mod_n(msg.as_ref());
+    let rhs = if m_reduced == [0u8; 32] {
+        r_pk
+    } else {
+        let msg_g = PublicKey::from_secret_key(secp, &SecretKey::from_slice(&m_reduced)?);
+        msg_g.combine(&r_pk)?
+    };
+    let lhs = r_prime.mul_tweak(secp, &Scalar::from(s_prime))?;
+
+    if lhs == rhs {
+        Ok(())
+    } else {
+        Err(Error::IncorrectSignature)
+    }
+}
+
+/// Completes `adaptor_sig` into a normal [`Signature`] using the decryption
+/// secret `y`, where `Y = y*G` is the adaptor point `adaptor_sig` was
+/// encrypted to.
+pub fn decrypt(adaptor_sig: &AdaptorSignature, y: &SecretKey) -> Result<Signature, Error> {
+    let r = adaptor_sig.r()?;
+    let s_prime = adaptor_sig.s_prime()?;
+
+    let y_inv = scalar_invert(y)?;
+    let s = s_prime.mul_tweak(&Scalar::from(y_inv))?;
+
+    let mut compact = [0u8; 64];
+    compact[..32].copy_from_slice(&point_x_bytes(&r));
+    compact[32..].copy_from_slice(&s.secret_bytes());
+    Signature::from_compact(&compact)
+}
+
+/// Extracts the decryption secret `y` from a completed `sig`, given the
+/// `adaptor_sig` it was decrypted from and the adaptor point `adaptor` it
+/// was encrypted to. This is the other half of the atomic-swap/DLC trick:
+/// publishing `sig` on chain reveals `y` to anyone holding `adaptor_sig`.
+/// Requires a signing-capable context (to check the recovered secret
+/// against `adaptor`).
+pub fn recover<C: Signing>(
+    secp: &Secp256k1<C>,
+    adaptor_sig: &AdaptorSignature,
+    sig: &Signature,
+    adaptor: &PublicKey,
+) -> Result<SecretKey, Error> {
+    let s_prime = adaptor_sig.s_prime()?;
+    let s = SecretKey::from_slice(&sig.serialize_compact()[32..64])?;
+    let s_inv = scalar_invert(&s)?;
+    let y = s_prime.mul_tweak(&Scalar::from(s_inv))?;
+
+    if PublicKey::from_secret_key(secp, &y) == *adaptor {
+        return Ok(y);
+    }
+    // `sig` may have been low-S normalized after decryption, which negates
+    // `s` (and so negates the recovered `y`); try the other root too.
+    let y_neg = y.negate();
+    if PublicKey::from_secret_key(secp, &y_neg) == *adaptor {
+        return Ok(y_neg);
+    }
+    Err(Error::InvalidSecretKey)
+}
+
+fn point_x_bytes(pk: &PublicKey) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&pk.serialize()[1..33]);
+    out
+}
+
+/// secp256k1's group order `n`, big-endian.
+const CURVE_ORDER: [u8; 32] = [
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE,
+    0xBA, 0xAE, 0xDC, 0xE6, 0xAF, 0x48, 0xA0, 0x3B, 0xBF, 0xD2, 0x5E, 0x8C, 0xD0, 0x36, 0x41, 0x41,
+];
+
+/// Reduces a 32-byte big-endian value mod the group order `n`. A message
+/// digest is just bytes: it can legitimately be `0` or `>= n`, and real
+/// ECDSA sign/verify handle that by reducing mod `n` rather than erroring
+/// (unlike [`SecretKey::from_slice`], which rejects both as invalid private
+/// key material). Since any value here is `< 2^256 < 2n`, a single
+/// conditional subtraction suffices.
+fn reduce_mod_n(bytes: &[u8]) -> [u8; 32] {
+    let mut buf = [0u8; 32];
+    buf.copy_from_slice(bytes);
+    if ge(&buf, &CURVE_ORDER) {
+        buf = sub(&buf, &CURVE_ORDER);
+    }
+    buf
+}
+
+fn ge(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    for i in 0..32 {
+        if a[i] != b[i] {
+            return a[i] > b[i];
+        }
+    }
+    true
+}
+
+fn sub(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    let mut borrow = 0i16;
+    for i in (0..32).rev() {
+        let diff = a[i] as i16 - b[i] as i16 - borrow;
+        if diff < 0 {
+            out[i] = (diff + 256) as u8;
+            borrow = 1;
+        } else {
+            out[i] = diff as u8;
+            borrow = 0;
+        }
+    }
+    out
+}
+
+/// `n - 2`, for inverting a scalar mod the group order `n` via Fermat's
+/// little theorem. `n` is secp256k1's well-known curve order.
+const CURVE_ORDER_MINUS_TWO: [u8; 32] = [
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE,
+    0xBA, 0xAE, 0xDC, 0xE6, 0xAF, 0x48, 0xA0, 0x3B, 0xBF, 0xD2, 0x5E, 0x8C, 0xD0, 0x36, 0x41, 0x3F,
+];
+
+/// Inverts `s` modulo the group order `n` as `s^(n-2) mod n`. The public API
+/// this crate exposes has no modular-inverse primitive, only `add_tweak`/
+/// `mul_tweak`, so this does textbook square-and-multiply exponentiation on
+/// top of `mul_tweak`.
+fn scalar_invert(s: &SecretKey) -> Result<SecretKey, Error> {
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    let mut result = SecretKey::from_slice(&one)?;
+    for byte in CURVE_ORDER_MINUS_TWO.iter() {
+        for bit_index in (0..8).rev() {
+            result = result.mul_tweak(&Scalar::from(result))?;
+            if (byte >> bit_index) & 1 == 1 {
+                result = result.mul_tweak(&Scalar::from(*s))?;
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Derives the signer's nonce `k`, deterministically from `msg`/`sk` via
+/// RFC6979, salted with the adaptor point so the same `(msg, sk)` pair
+/// never reuses a nonce across different adaptor points.
+fn signing_nonce(msg: &Message, sk: &SecretKey, adaptor: &PublicKey) -> Result<SecretKey, Error> {
+    let tag = sha256(&adaptor.serialize());
+    SecretKey::from_slice(&rfc6979_nonce(msg, sk, Some(&tag)))
+}
+
+/// Derives the DLEQ proof's own nonce `j`, deterministically from the
+/// signing nonce `k` and the adaptor point (distinct domain from
+/// `signing_nonce`, so it is an independent value).
+fn dleq_nonce(k: &SecretKey, adaptor: &PublicKey) -> Result<SecretKey, Error> {
+    let mut buf = [0u8; 32 + 33];
+    buf[..32].copy_from_slice(&k.secret_bytes());
+    buf[32..].copy_from_slice(&adaptor.serialize());
+    SecretKey::from_slice(&sha256(&buf))
+}
+
+/// Computes the Fiat-Shamir challenge for the DLEQ proof that `r_prime` and
+/// `r` share the same discrete log relative to `G` and `adaptor`
+/// respectively, given the prover's commitments `a1 = j*G`, `a2 = j*adaptor`.
+fn dleq_challenge(
+    adaptor: &PublicKey,
+    r_prime: &PublicKey,
+    r: &PublicKey,
+    a1: &PublicKey,
+    a2: &PublicKey,
+) -> Result<SecretKey, Error> {
+    let mut buf = [0u8; 33 * 5];
+    buf[0..33].copy_from_slice(&adaptor.serialize());
+    buf[33..66].copy_from_slice(&r_prime.serialize());
+    buf[66..99].copy_from_slice(&r.serialize());
+    buf[99..132].copy_from_slice(&a1.serialize());
+    buf[132..165].copy_from_slice(&a2.serialize());
+    SecretKey::from_slice(&sha256(&buf))
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adaptor_round_trip() {
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_slice(&[0x3d; 32]).unwrap();
+        let pk = PublicKey::from_secret_key(&secp, &sk);
+        let msg = Message::from_slice(&[0x61; 32]).unwrap();
+        let y = SecretKey::from_slice(&[0xe7; 32]).unwrap();
+        let adaptor = PublicKey::from_secret_key(&secp, &y);
+
+        let adaptor_sig = encrypt(&secp, &msg, &sk, &adaptor).unwrap();
+        assert_eq!(verify(&secp, &adaptor_sig, &pk, &msg, &adaptor), Ok(()));
+
+        let sig = decrypt(&adaptor_sig, &y).unwrap();
+        assert_eq!(secp.verify_ecdsa(&msg, &sig, &pk), Ok(()));
+
+        let recovered = recover(&secp, &adaptor_sig, &sig, &adaptor).unwrap();
+        assert_eq!(recovered.secret_bytes(), y.secret_bytes());
+    }
+
+    #[test]
+    fn verify_rejects_wrong_adaptor_point() {
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_slice(&[0x4b; 32]).unwrap();
+        let pk = PublicKey::from_secret_key(&secp, &sk);
+        let msg = Message::from_slice(&[0xd2; 32]).unwrap();
+        let y = SecretKey::from_slice(&[0x29; 32]).unwrap();
+        let adaptor = PublicKey::from_secret_key(&secp, &y);
+
+        let adaptor_sig = encrypt(&secp, &msg, &sk, &adaptor).unwrap();
+
+        let other_y = SecretKey::from_slice(&[0x85; 32]).unwrap();
+        let other_adaptor = PublicKey::from_secret_key(&secp, &other_y);
+        assert_eq!(
+            verify(&secp, &adaptor_sig, &pk, &msg, &other_adaptor),
+            Err(Error::IncorrectSignature)
+        );
+    }
+
+    #[test]
+    fn decrypt_with_wrong_secret_does_not_verify() {
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_slice(&[0x72; 32]).unwrap();
+        let pk = PublicKey::from_secret_key(&secp, &sk);
+        let msg = Message::from_slice(&[0x0e; 32]).unwrap();
+        let y = SecretKey::from_slice(&[0x5a; 32]).unwrap();
+        let adaptor = PublicKey::from_secret_key(&secp, &y);
+
+        let adaptor_sig = encrypt(&secp, &msg, &sk, &adaptor).unwrap();
+
+        let wrong_y = SecretKey::from_slice(&[0x9f; 32]).unwrap();
+        let bad_sig = decrypt(&adaptor_sig, &wrong_y).unwrap();
+        assert_eq!(secp.verify_ecdsa(&msg, &bad_sig, &pk), Err(Error::IncorrectSignature));
+    }
+
+    #[test]
+    fn encrypt_and_verify_handle_all_zero_message() {
+        // `m == 0` can't be represented as `m*G` (the point at infinity), so
+        // this exercises the identity-skip path in `verify` rather than
+        // erroring the way `SecretKey::from_slice(msg)` used to.
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_slice(&[0x37; 32]).unwrap();
+        let pk = PublicKey::from_secret_key(&secp, &sk);
+        let msg = Message::from_slice(&[0x00; 32]).unwrap();
+        let y = SecretKey::from_slice(&[0xc6; 32]).unwrap();
+        let adaptor = PublicKey::from_secret_key(&secp, &y);
+
+        let adaptor_sig = encrypt(&secp, &msg, &sk, &adaptor).unwrap();
+        assert_eq!(verify(&secp, &adaptor_sig, &pk, &msg, &adaptor), Ok(()));
+
+        let sig = decrypt(&adaptor_sig, &y).unwrap();
+        assert_eq!(secp.verify_ecdsa(&msg, &sig, &pk), Ok(()));
+    }
+}
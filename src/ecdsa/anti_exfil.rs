@@ -0,0 +1,200 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Anti-exfil (anti-covert-channel) ECDSA signing.
+//!
+//! This implements the two-round protocol described in the "Anti-Klepto"
+//! proposal for hardware wallets: a host that does not trust its signer to
+//! be honest can force the signer's nonce to depend on host-supplied
+//! randomness that the signer commits to *before* it is revealed. Because
+//! the signer cannot predict the host's contribution when it makes its
+//! commitment, it has no way to bias the final nonce to leak secret bits
+//! through it, which is the covert channel a malicious or compromised
+//! signing device would otherwise have available.
+//!
+//! The five steps of the protocol are, in order: [`host_commit`] (host),
+//! [`signer_commit`] (signer), the host revealing its randomness out of
+//! band, [`sign_anti_exfil`] (signer), and finally [`anti_exfil_verify`]
+//! (host).
+
+use super::util::{rfc6979_nonce, sha256};
+use super::{EcdsaNonce, Signature};
+use crate::{Error, Message, PublicKey, Scalar, Secp256k1, SecretKey, Signing, Verification};
+
+/// A host's commitment to 32 bytes of randomness, sent to the signer before
+/// the signer reveals its nonce commitment.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct HostCommitment([u8; 32]);
+
+impl HostCommitment {
+    /// Returns the raw commitment bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+/// The signer's commitment to its nonce, sent to the host before the host
+/// reveals the randomness behind its [`HostCommitment`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct SignerCommitment(PublicKey);
+
+impl SignerCommitment {
+    /// Returns the committed-to nonce point.
+    #[inline]
+    pub fn as_point(&self) -> &PublicKey {
+        &self.0
+    }
+}
+
+/// Computes the host's commitment to `host_rand`, the 32 bytes of randomness
+/// it will mix into the signer's nonce once it has seen the signer's
+/// commitment.
+pub fn host_commit(host_rand: &[u8; 32]) -> HostCommitment {
+    HostCommitment(sha256(host_rand))
+}
+
+/// Computes the signer's commitment to the nonce it intends to use, derived
+/// deterministically from `msg`/`sk` via RFC6979 and tweaked with the host's
+/// commitment so that the same (message, key) pair never produces the same
+/// commitment for two different anti-exfil sessions.
+pub fn signer_commit<C: Signing>(
+    secp: &Secp256k1<C>,
+    msg: &Message,
+    sk: &SecretKey,
+    host_commitment: &HostCommitment,
+) -> Result<SignerCommitment, Error> {
+    let nonce32 = rfc6979_nonce(msg, sk, Some(host_commitment.as_bytes()));
+    let nonce_sk = SecretKey::from_slice(&nonce32)?;
+    Ok(SignerCommitment(PublicKey::from_secret_key(secp, &nonce_sk)))
+}
+
+/// Produces the final signature for `msg` once the host has revealed
+/// `host_rand`. The nonce used is the signer's committed-to RFC6979 nonce
+/// with `host_rand` added to it (mod the group order), so the final nonce is
+/// provably influenced by randomness the signer could not have predicted
+/// when it sent its [`SignerCommitment`].
+///
+/// `host_rand` must be the reveal of `host_commitment` (i.e.
+/// `host_commit(host_rand) == *host_commitment`); this is checked before
+/// anything else, so a caller bug that mixes up rounds and passes a reveal
+/// for a different commitment fails loudly here instead of silently
+/// producing a signature.
+pub fn sign_anti_exfil<C: Signing>(
+    secp: &Secp256k1<C>,
+    msg: &Message,
+    sk: &SecretKey,
+    host_commitment: &HostCommitment,
+    host_rand: &[u8; 32],
+) -> Result<Signature, Error> {
+    if host_commit(host_rand) != *host_commitment {
+        return Err(Error::InvalidTweak);
+    }
+
+    let nonce32 = rfc6979_nonce(msg, sk, Some(host_commitment.as_bytes()));
+    let final_nonce = SecretKey::from_slice(&nonce32)?
+        .add_tweak(&Scalar::from_be_bytes(*host_rand).map_err(|_| Error::InvalidTweak)?)?;
+    secp.sign_ecdsa_with_nonce(msg, sk, &FixedNonce(final_nonce))
+}
+
+/// Confirms that `sig` is a valid signature for `msg` under `pubkey`, and
+/// that its nonce is exactly the signer's committed-to nonce combined with
+/// the host's revealed randomness. A signer that deviates from the
+/// committed nonce (for example, to leak bits of `sk` through a biased
+/// nonce) is caught here.
+pub fn anti_exfil_verify<C: Verification>(
+    secp: &Secp256k1<C>,
+    sig: &Signature,
+    msg: &Message,
+    pubkey: &PublicKey,
+    signer_commitment: &SignerCommitment,
+    host_rand: &[u8; 32],
+) -> Result<(), Error> {
+    secp.verify_ecdsa(msg, sig, pubkey)?;
+
+    let tweak = Scalar::from_be_bytes(*host_rand).map_err(|_| Error::InvalidTweak)?;
+    let expected_r = signer_commitment.0.add_exp_tweak(secp, &tweak)?;
+
+    let expected_r_x = &expected_r.serialize()[1..33];
+    let sig_r = &sig.serialize_compact()[..32];
+    if expected_r_x == sig_r {
+        Ok(())
+    } else {
+        Err(Error::IncorrectSignature)
+    }
+}
+
+/// An [`EcdsaNonce`] that always returns the same, already-computed nonce.
+/// Used internally to hand a precomputed anti-exfil nonce to
+/// [`Secp256k1::sign_ecdsa_with_nonce`].
+struct FixedNonce(SecretKey);
+
+impl EcdsaNonce for FixedNonce {
+    fn nonce(
+        &self,
+        _msg32: &[u8; 32],
+        _key32: &[u8; 32],
+        _algo16: Option<&[u8; 16]>,
+        _attempt: u32,
+        _extra: Option<&[u8]>,
+    ) -> Option<[u8; 32]> {
+        Some(self.0.secret_bytes())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anti_exfil_round_trip() {
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_slice(&[0x51; 32]).unwrap();
+        let pk = PublicKey::from_secret_key(&secp, &sk);
+        let msg = Message::from_slice(&[0x3c; 32]).unwrap();
+        let host_rand = [0xc4; 32];
+
+        let commitment = host_commit(&host_rand);
+        let signer_commitment = signer_commit(&secp, &msg, &sk, &commitment).unwrap();
+        let sig = sign_anti_exfil(&secp, &msg, &sk, &commitment, &host_rand).unwrap();
+
+        assert_eq!(
+            anti_exfil_verify(&secp, &sig, &msg, &pk, &signer_commitment, &host_rand),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn sign_anti_exfil_rejects_mismatched_reveal() {
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_slice(&[0x19; 32]).unwrap();
+        let msg = Message::from_slice(&[0x6d; 32]).unwrap();
+
+        let commitment = host_commit(&[0x2a; 32]);
+        // `wrong_host_rand` below does not hash to `commitment`.
+        let wrong_host_rand = [0x7e; 32];
+
+        assert_eq!(
+            sign_anti_exfil(&secp, &msg, &sk, &commitment, &wrong_host_rand),
+            Err(Error::InvalidTweak)
+        );
+    }
+
+    #[test]
+    fn anti_exfil_verify_rejects_wrong_host_rand() {
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_slice(&[0x8d; 32]).unwrap();
+        let pk = PublicKey::from_secret_key(&secp, &sk);
+        let msg = Message::from_slice(&[0xf5; 32]).unwrap();
+        let host_rand = [0x16; 32];
+
+        let commitment = host_commit(&host_rand);
+        let signer_commitment = signer_commit(&secp, &msg, &sk, &commitment).unwrap();
+        let sig = sign_anti_exfil(&secp, &msg, &sk, &commitment, &host_rand).unwrap();
+
+        let other_host_rand = [0x93; 32];
+        assert_eq!(
+            anti_exfil_verify(&secp, &sig, &msg, &pk, &signer_commitment, &other_host_rand),
+            Err(Error::IncorrectSignature)
+        );
+    }
+}
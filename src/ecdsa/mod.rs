@@ -5,7 +5,16 @@ use core::{fmt, str, ptr};
 use crate::{Signing, Verification, Message, PublicKey, Secp256k1, SecretKey, from_hex, Error, ffi};
 use crate::ffi::CPtr;
 
+#[cfg(feature = "std")]
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
 pub mod serialized_signature;
+pub mod anti_exfil;
+mod util;
+
+#[cfg(feature = "unstable-adaptor-signatures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unstable-adaptor-signatures")))]
+pub mod adaptor;
 
 #[cfg(feature = "recovery")]
 mod recovery;
@@ -130,18 +139,40 @@ impl Signature {
     /// valid. (For example, parsing the historic Bitcoin blockchain requires
     /// this.) For these applications we provide this normalization function,
     /// which ensures that the s value lies in the lower half of its range.
-    pub fn normalize_s(&mut self) {
+    ///
+    /// Returns `true` if the signature was changed (i.e. it previously had a
+    /// high S-value), `false` if it was already normalized.
+    pub fn normalize_s(&mut self) -> bool {
         unsafe {
-            // Ignore return value, which indicates whether the sig
-            // was already normalized. We don't care.
             ffi::secp256k1_ecdsa_signature_normalize(
                 ffi::secp256k1_context_no_precomp,
                 self.as_mut_c_ptr(),
                 self.as_c_ptr(),
-            );
+            ) == 1
         }
     }
 
+    /// Returns `true` if this signature's S-value already lies in the lower
+    /// half of the field range, i.e. [`normalize_s`](Signature::normalize_s)
+    /// would be a no-op.
+    pub fn is_normalized(&self) -> bool {
+        let mut copy = *self;
+        unsafe {
+            ffi::secp256k1_ecdsa_signature_normalize(
+                ffi::secp256k1_context_no_precomp,
+                copy.as_mut_c_ptr(),
+                self.as_c_ptr(),
+            ) == 0
+        }
+    }
+
+    /// Alias for [`is_normalized`](Signature::is_normalized): returns `true`
+    /// if this signature's S-value is already in its canonical "low S" form.
+    #[inline]
+    pub fn is_low_s(&self) -> bool {
+        self.is_normalized()
+    }
+
     /// Obtains a raw pointer suitable for use with FFI functions
     #[inline]
     pub fn as_ptr(&self) -> *const ffi::Signature {
@@ -245,6 +276,103 @@ impl<'de> serde::Deserialize<'de> for Signature {
     }
 }
 
+/// A user-supplied nonce-generation scheme for ECDSA signing.
+///
+/// Implementing this trait lets callers replace libsecp256k1's built-in
+/// RFC6979 nonce derivation with their own (a deterministic counter, an
+/// alternative hash-based construction, or a nonce sourced from a hardware
+/// signer) without forking the crate. Nonce generation happens entirely on
+/// the C side of the FFI boundary, so implementations are invoked through
+/// an `extern "C"` trampoline; see [`Secp256k1::sign_ecdsa_with_nonce`].
+pub trait EcdsaNonce {
+    /// Derives a 32-byte nonce for the given message/key pair.
+    ///
+    /// `algo16` is the 16-byte algorithm tag libsecp256k1 passes to
+    /// distinguish nonce functions used for different purposes (it is
+    /// `None` when the C caller passes a null tag). `attempt` counts the
+    /// number of times this function has been called for the current
+    /// signing operation, starting at 0. `extra` is always `None` when
+    /// called through [`Secp256k1::sign_ecdsa_with_nonce`], since the
+    /// callback's data pointer carries the implementor itself.
+    ///
+    /// Returning `None` tells libsecp256k1 that no usable nonce could be
+    /// produced for this `attempt`. Unlike the internal retry libsecp256k1
+    /// performs when a successfully-generated nonce happens to yield an
+    /// invalid `r` or `s` (which the caller never observes), a `None` here
+    /// is NOT retried: `secp256k1_ecdsa_sign` aborts the whole signing
+    /// operation and reports failure immediately. Callers that use this to
+    /// reject an attempt (or whose nonce source is flaky) should expect
+    /// [`Secp256k1::sign_ecdsa_with_nonce`] to return `Err` rather than a
+    /// signature.
+    ///
+    /// Implementations should prefer returning `None` over panicking: with
+    /// the `std` feature enabled a panic here is caught and treated as
+    /// `None`, but `no_std` builds have no way to catch it and it will
+    /// behave as any other panic underneath an `extern "C"` callback
+    /// (typically an abort).
+    fn nonce(
+        &self,
+        msg32: &[u8; 32],
+        key32: &[u8; 32],
+        algo16: Option<&[u8; 16]>,
+        attempt: u32,
+        extra: Option<&[u8]>,
+    ) -> Option<[u8; 32]>;
+}
+
+/// `extern "C"` trampoline matching libsecp256k1's `secp256k1_nonce_function`
+/// signature, forwarding into the [`EcdsaNonce`] implementor whose address
+/// was passed through `data`.
+///
+/// `N::nonce` is arbitrary caller code (e.g. a hardware-backed nonce source
+/// unwrapping an I/O error) running underneath an `extern "C"` callback that
+/// libsecp256k1 invokes directly; unwinding across that boundary is
+/// undefined behavior and would abort the process instead of surfacing as
+/// the `Err` this API exists to produce. With the `std` feature enabled, a
+/// panic from `N::nonce` is caught here and turned into the same `None`
+/// (nonce-generation-failed) outcome a well-behaved implementation would
+/// return. `no_std` builds have no unwinding mechanism to catch, so a panic
+/// there behaves as it always would (typically an abort).
+unsafe extern "C" fn nonce_function_trampoline<N: EcdsaNonce>(
+    nonce32: *mut ffi::types::c_uchar,
+    msg32: *const ffi::types::c_uchar,
+    key32: *const ffi::types::c_uchar,
+    algo16: *const ffi::types::c_uchar,
+    data: *const ffi::types::c_void,
+    attempt: ffi::types::c_uint,
+) -> ffi::types::c_int {
+    let nonce_fn = &*(data as *const N);
+
+    let mut msg32_buf = [0u8; 32];
+    ptr::copy_nonoverlapping(msg32, msg32_buf.as_mut_ptr(), 32);
+    let mut key32_buf = [0u8; 32];
+    ptr::copy_nonoverlapping(key32, key32_buf.as_mut_ptr(), 32);
+
+    let mut algo16_buf = [0u8; 16];
+    let algo16_opt = if algo16.is_null() {
+        None
+    } else {
+        ptr::copy_nonoverlapping(algo16, algo16_buf.as_mut_ptr(), 16);
+        Some(&algo16_buf)
+    };
+
+    #[cfg(feature = "std")]
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        nonce_fn.nonce(&msg32_buf, &key32_buf, algo16_opt, attempt as u32, None)
+    }))
+    .unwrap_or(None);
+    #[cfg(not(feature = "std"))]
+    let result = nonce_fn.nonce(&msg32_buf, &key32_buf, algo16_opt, attempt as u32, None);
+
+    match result {
+        Some(nonce) => {
+            ptr::copy_nonoverlapping(nonce.as_ptr(), nonce32, 32);
+            1
+        }
+        None => 0,
+    }
+}
+
 impl<C: Signing> Secp256k1<C> {
 
     /// Constructs a signature for `msg` using the secret key `sk` and RFC6979 nonce
@@ -292,6 +420,35 @@ impl<C: Signing> Secp256k1<C> {
         self.sign_ecdsa_with_noncedata_pointer(msg, sk, noncedata_ptr)
     }
 
+    /// Constructs a signature for `msg` using the secret key `sk`, deriving
+    /// the nonce with the user-supplied `nonce` implementation of
+    /// [`EcdsaNonce`] instead of the default RFC6979 scheme.
+    /// Requires a signing-capable context.
+    ///
+    /// Unlike [`sign_ecdsa`](Self::sign_ecdsa), this can fail: if `nonce`
+    /// returns `None` for any attempt, `secp256k1_ecdsa_sign` reports
+    /// failure immediately rather than retrying, and this returns
+    /// `Err(Error::InvalidSignature)`.
+    pub fn sign_ecdsa_with_nonce<N: EcdsaNonce>(
+        &self,
+        msg: &Message,
+        sk: &SecretKey,
+        nonce: &N,
+    ) -> Result<Signature, Error> {
+        unsafe {
+            let mut ret = ffi::Signature::new();
+            let data = nonce as *const N as *const ffi::types::c_void;
+            if ffi::secp256k1_ecdsa_sign(self.ctx, &mut ret, msg.as_c_ptr(),
+                                          sk.as_c_ptr(), nonce_function_trampoline::<N>,
+                                          data) == 1
+            {
+                Ok(Signature::from(ret))
+            } else {
+                Err(Error::InvalidSignature)
+            }
+        }
+    }
+
     fn sign_grind_with_check(
         &self, msg: &Message,
         sk: &SecretKey,
@@ -427,6 +584,19 @@ impl<C: Verification> Secp256k1<C> {
             }
         }
     }
+
+    /// Like [`verify_ecdsa`](Self::verify_ecdsa), but additionally rejects
+    /// signatures whose S-value lies in the upper half of the field range.
+    /// Systems that require canonical, non-malleable signatures (e.g.
+    /// BIP-146-style low-S enforcement) should use this instead of
+    /// `verify_ecdsa` plus a manual check on the serialized bytes.
+    #[inline]
+    pub fn verify_ecdsa_strict(&self, msg: &Message, sig: &Signature, pk: &PublicKey) -> Result<(), Error> {
+        if !sig.is_normalized() {
+            return Err(Error::IncorrectSignature);
+        }
+        self.verify_ecdsa(msg, sig, pk)
+    }
 }
 
 pub(crate) fn compact_sig_has_zero_first_bit(sig: &ffi::Signature) -> bool {
@@ -456,3 +626,133 @@ pub(crate) fn der_length_check(sig: &ffi::Signature, max_len: usize) -> bool {
     }
     len <= max_len
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    /// An [`EcdsaNonce`] that always returns the same fixed nonce.
+    struct ConstantNonce(pub [u8; 32]);
+
+    impl EcdsaNonce for ConstantNonce {
+        fn nonce(
+            &self,
+            _msg32: &[u8; 32],
+            _key32: &[u8; 32],
+            _algo16: Option<&[u8; 16]>,
+            _attempt: u32,
+            _extra: Option<&[u8]>,
+        ) -> Option<[u8; 32]> {
+            Some(self.0)
+        }
+    }
+
+    /// An [`EcdsaNonce`] that always refuses to produce a nonce, simulating
+    /// a flaky hardware nonce source.
+    struct NeverNonce;
+
+    impl EcdsaNonce for NeverNonce {
+        fn nonce(
+            &self,
+            _msg32: &[u8; 32],
+            _key32: &[u8; 32],
+            _algo16: Option<&[u8; 16]>,
+            _attempt: u32,
+            _extra: Option<&[u8]>,
+        ) -> Option<[u8; 32]> {
+            None
+        }
+    }
+
+    /// An [`EcdsaNonce`] whose implementation panics, simulating a bug in a
+    /// caller-supplied nonce source (e.g. an unwrapped I/O error from a
+    /// hardware signer).
+    struct PanickingNonce;
+
+    impl EcdsaNonce for PanickingNonce {
+        fn nonce(
+            &self,
+            _msg32: &[u8; 32],
+            _key32: &[u8; 32],
+            _algo16: Option<&[u8; 16]>,
+            _attempt: u32,
+            _extra: Option<&[u8]>,
+        ) -> Option<[u8; 32]> {
+            panic!("hardware nonce source is unavailable")
+        }
+    }
+
+    #[test]
+    fn sign_ecdsa_with_nonce_roundtrips() {
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_slice(&[0xa1; 32]).unwrap();
+        let pk = PublicKey::from_secret_key(&secp, &sk);
+        let msg = Message::from_slice(&[0x5c; 32]).unwrap();
+
+        let sig = secp
+            .sign_ecdsa_with_nonce(&msg, &sk, &ConstantNonce([0x77; 32]))
+            .expect("ConstantNonce always returns Some");
+        assert_eq!(secp.verify_ecdsa(&msg, &sig, &pk), Ok(()));
+    }
+
+    #[test]
+    fn sign_ecdsa_with_nonce_propagates_none_as_err() {
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_slice(&[0x2f; 32]).unwrap();
+        let msg = Message::from_slice(&[0x9e; 32]).unwrap();
+
+        assert_eq!(
+            secp.sign_ecdsa_with_nonce(&msg, &sk, &NeverNonce),
+            Err(Error::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn sign_ecdsa_with_nonce_catches_panic_and_returns_err() {
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_slice(&[0x08; 32]).unwrap();
+        let msg = Message::from_slice(&[0xf1; 32]).unwrap();
+
+        // The panic must not unwind across the `extern "C"` callback into
+        // libsecp256k1; it should surface as a plain `Err` here instead of
+        // aborting the test process.
+        assert_eq!(
+            secp.sign_ecdsa_with_nonce(&msg, &sk, &PanickingNonce),
+            Err(Error::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn verify_ecdsa_strict_rejects_high_s_that_verify_ecdsa_accepts() {
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_slice(&[0x64; 32]).unwrap();
+        let pk = PublicKey::from_secret_key(&secp, &sk);
+        let msg = Message::from_slice(&[0xd3; 32]).unwrap();
+
+        let sig = secp.sign_ecdsa(&msg, &sk);
+        assert!(sig.is_low_s());
+        assert_eq!(secp.verify_ecdsa_strict(&msg, &sig, &pk), Ok(()));
+
+        // libsecp256k1 always signs with the low-S representative, so build
+        // the high-S representative of the same (r, s) by hand to exercise
+        // the rejection path.
+        let compact = sig.serialize_compact();
+        let s = SecretKey::from_slice(&compact[32..]).unwrap();
+        let mut flipped = [0u8; 64];
+        flipped[..32].copy_from_slice(&compact[..32]);
+        flipped[32..].copy_from_slice(&s.negate().secret_bytes());
+        let mut high_s_sig = Signature::from_compact(&flipped).unwrap();
+
+        assert!(!high_s_sig.is_normalized());
+        // Still a valid signature under plain ECDSA verification...
+        assert_eq!(secp.verify_ecdsa(&msg, &high_s_sig, &pk), Ok(()));
+        // ...but rejected by the strict, non-malleable check.
+        assert_eq!(
+            secp.verify_ecdsa_strict(&msg, &high_s_sig, &pk),
+            Err(Error::IncorrectSignature)
+        );
+
+        assert!(high_s_sig.normalize_s());
+        assert_eq!(high_s_sig, sig);
+    }
+}
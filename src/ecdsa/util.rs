@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Internal helpers shared by [`super::anti_exfil`] and [`super::adaptor`]:
+//! a thin wrapper around libsecp256k1's own RFC6979 nonce function, and a
+//! small self-contained SHA256 (FIPS 180-4) so neither module needs a
+//! hashing dependency just to derive nonce salts and Fiat-Shamir challenges.
+//! Kept in one place so the two callers can't drift out of sync on a
+//! security-sensitive primitive.
+
+use core::ptr;
+
+use crate::ffi::CPtr;
+use crate::{ffi, Message, SecretKey};
+
+/// Calls libsecp256k1's own RFC6979 nonce function directly so callers can
+/// obtain the nonce scalar itself rather than a full signature, optionally
+/// mixing in 32 bytes of extra entropy exactly as
+/// [`Secp256k1::sign_ecdsa_with_noncedata`](crate::Secp256k1::sign_ecdsa_with_noncedata)
+/// does.
+pub(crate) fn rfc6979_nonce(msg: &Message, sk: &SecretKey, extra: Option<&[u8; 32]>) -> [u8; 32] {
+    let mut nonce32 = [0u8; 32];
+    let extra_ptr = match extra {
+        Some(e) => e.as_ptr() as *const ffi::types::c_void,
+        None => ptr::null(),
+    };
+    unsafe {
+        let ret = ffi::secp256k1_nonce_function_rfc6979(
+            nonce32.as_mut_c_ptr(),
+            msg.as_c_ptr(),
+            sk.as_c_ptr(),
+            ptr::null(),
+            extra_ptr,
+            0,
+        );
+        debug_assert_eq!(ret, 1);
+    }
+    nonce32
+}
+
+/// A small self-contained SHA256 (FIPS 180-4) implementation. `data` must fit,
+/// with its padding, in the fixed 256-byte (four-block) buffer below, i.e. be
+/// at most 247 bytes long; both current callers hash at most a few
+/// concatenated points and 32-byte values, well within that.
+pub(crate) fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+        0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+        0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+        0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+        0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+        0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+        0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+    ];
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+
+    let mut msg = [0u8; 256];
+    let bit_len = (data.len() as u64) * 8;
+    let mut padded_len = data.len() + 9;
+    if padded_len % 64 != 0 {
+        padded_len += 64 - (padded_len % 64);
+    }
+    debug_assert!(padded_len <= msg.len());
+    msg[..data.len()].copy_from_slice(data);
+    msg[data.len()] = 0x80;
+    msg[padded_len - 8..padded_len].copy_from_slice(&bit_len.to_be_bytes());
+
+    for chunk in msg[..padded_len].chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for i in 0..16 {
+            w[i] = u32::from_be_bytes([chunk[4 * i], chunk[4 * i + 1], chunk[4 * i + 2], chunk[4 * i + 3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let (mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh) =
+            (h[0], h[1], h[2], h[3], h[4], h[5], h[6], h[7]);
+
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ ((!e) & g);
+            let temp1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(maj);
+
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+
+        h[0] = h[0].wrapping_add(a);
+        h[1] = h[1].wrapping_add(b);
+        h[2] = h[2].wrapping_add(c);
+        h[3] = h[3].wrapping_add(d);
+        h[4] = h[4].wrapping_add(e);
+        h[5] = h[5].wrapping_add(f);
+        h[6] = h[6].wrapping_add(g);
+        h[7] = h[7].wrapping_add(hh);
+    }
+
+    let mut out = [0u8; 32];
+    for (i, word) in h.iter().enumerate() {
+        out[4 * i..4 * i + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}